@@ -7,53 +7,747 @@ use scrypto::prelude::*;
 
 // Things we will learn:
 // 1. How to create resources in Scrypto.
-// 2. How to use Badge system to set access control. 
+// 2. How to use Badge system to set access control.
 
+/// How many epochs a freshly approved UserBadge is valid for before it needs renewing.
+const DEFAULT_MEMBERSHIP_EPOCHS: u64 = 1_000;
 
-// This defines our blueprint design that defines the logic of our component. 
+/// The tier a UserBadge is assigned at approval time, before a custodian upgrades/downgrades it.
+const DEFAULT_TIER: &str = "Standard";
+
+/// This is the NFT struct that will define our temporary badge.
+/// The temporary badge will be given to users who request to be a member of this protocol.
+/// The temporary badge is mainly used as a queue to await approval and claim the User Badge to access this
+/// protocol when the member is approved.
+#[derive(NonFungibleData)]
+pub struct TemporaryBadge {
+    username: String
+}
+
+/// This is the NFT struct that will define our user badge.
+/// User badge are approved members of this protocol and members can provide this badge to access
+/// authorized features of this protocol.
+#[derive(NonFungibleData)]
+pub struct UserBadge {
+    username: String,
+    // Mutable: membership lapses once the current epoch reaches this value. Extended by
+    // "renew_membership".
+    #[scrypto(mutable)]
+    expiry_epoch: u64,
+    // Mutable: a custodian-assigned membership tier, e.g. "Standard". Changed by "set_tier".
+    #[scrypto(mutable)]
+    tier: String,
+}
+
+/// The top tier of the admin hierarchy. Exactly one CustodianBadge is minted, at instantiation,
+/// and it can do everything an Operator can plus manage Operators and protocol-wide controls
+/// (pause/ban). Tracked as a `NonFungibleGlobalId` rather than a bare `ResourceAddress` because
+/// access rules need to pin down this *specific* badge, not merely "any badge of this resource".
+#[derive(NonFungibleData)]
+pub struct CustodianBadge {}
+
+/// A delegated admin badge a Custodian can mint or revoke. Operators are restricted to
+/// `approve_user`, `reject_user` and `renew_membership` - they cannot pause the protocol, manage
+/// the denylist, set tiers, or mint further Operators.
+#[derive(NonFungibleData)]
+pub struct OperatorBadge {}
+
+/// Describes a resource amount involved in an auction - either side of the trade - as either a
+/// fungible amount or a specific set of non-fungible ids of a resource. Mirrors the escrow
+/// exercise's `EscrowResourceSpecifier`, letting `create_auction` sell either fungible tokens or
+/// NFTs and accept payment in an arbitrary resource.
+///
+/// NOTE: the request that introduced this enum also asked for `auction_vault`/`bid_vault` to be
+/// split into the specialized `FungibleVault`/`NonFungibleVault` container types, dispatching on
+/// this enum for deposits/validation/payout. That type split does not exist in this blueprint's
+/// Scrypto version - this whole file is written against the `blueprint!` macro, `NonFungibleId`,
+/// and `borrow_resource_manager!`, none of which coexist with `FungibleVault`/`NonFungibleVault`
+/// in any real Scrypto release. Implementing it would mean rewriting this blueprint off the
+/// `blueprint!` macro entirely, which is a far larger and unrelated change. `auction_vault` and
+/// `bid_vault` stay plain `Vault`s; `get_resource_address()`/`matches()` below are what `create_auction`
+/// and `place_bid` use instead to validate against the right resource. Flagging this
+/// deviation explicitly since the request called the vault split out as a requirement.
+#[derive(TypeId, Encode, Decode, Describe)]
+pub enum AuctionResourceSpecifier {
+    Fungible {
+        resource_address: ResourceAddress,
+        amount: Decimal,
+    },
+    NonFungible {
+        resource_address: ResourceAddress,
+        ids: BTreeSet<NonFungibleId>,
+    },
+}
+
+impl AuctionResourceSpecifier {
+
+    /// The ResourceAddress this specifier describes, regardless of variant.
+    pub fn get_resource_address(&self) -> ResourceAddress {
+        match self {
+            AuctionResourceSpecifier::Fungible { resource_address, .. } => *resource_address,
+            AuctionResourceSpecifier::NonFungible { resource_address, .. } => *resource_address,
+        }
+    }
+
+    /// Whether a Bucket exactly satisfies this specifier - same resource, and either the same
+    /// fungible amount or the same set of non-fungible ids.
+    pub fn matches(&self, bucket: &Bucket) -> bool {
+        match self {
+            AuctionResourceSpecifier::Fungible { resource_address, amount } => {
+                bucket.resource_address() == *resource_address && bucket.amount() == *amount
+            }
+            AuctionResourceSpecifier::NonFungible { resource_address, ids } => {
+                bucket.resource_address() == *resource_address && &bucket.non_fungible_ids() == ids
+            }
+        }
+    }
+}
+
+/// This is the NFT handed out in exchange for a bid placed on the active auction. A bidder holds
+/// onto it (it deposits into their account like any other NFT) until the auction ends, then
+/// redeems it through `reclaim_bid` for either the auctioned lot (if it was the winning bid) or a
+/// refund. Minting and burning are gated the same way `UserBadge`/`TemporaryBadge` are, via
+/// `component_badge_vault.authorize`.
+///
+/// NOTE: this is NOT the transient-badge pattern the original request asked for (a
+/// `restrict_deposit(deny_all)` resource that forces mint-and-burn within a single transaction).
+/// That pattern is incompatible with this auction: a bid has to outlive the transaction that
+/// places it and survive until some later transaction closes out the auction, which is exactly
+/// what `deny_all` prevents. An earlier version of this NFT did carry that restriction and it made
+/// every `place_bid` call permanently uncommittable - see the fix that dropped it. Flagging the
+/// deviation here explicitly since the transient-badge pattern was the headline ask of the
+/// request that introduced this auction subsystem.
+#[derive(NonFungibleData)]
+pub struct BidReceipt {
+    bid_amount: Decimal,
+}
+
+// This defines our blueprint design that defines the logic of our component.
 blueprint! {
     /// This struct defines the type of vaults and data that our component will hold.
     /// In a permissioned protocol we will want to have some sort of admin badge that will be given to us to
     /// allow us to access permissioned method calls such as approve users who request to be members of this protocol.
     struct UserAuth {
-        // This is the ResourceAddress of the admin badge that will allow us to access permissioned method call.
-        admin_badge_address: ResourceAddress,
+        // The resource address and local id of the single CustodianBadge minted at instantiation.
+        // Stored as a NonFungibleGlobalId (rather than a bare ResourceAddress) so access rules can
+        // require this specific badge instance.
+        custodian_badge: NonFungibleGlobalId,
+        // The ResourceAddress of OperatorBadge NFTs. Any number of these can be minted by a
+        // custodian via "add_operator".
+        operator_badge_address: ResourceAddress,
+        // Tracks which OperatorBadge NFT ids are currently delegated by a custodian. This is the
+        // actual source of truth for operator authorization: "add_operator"/"remove_operator"
+        // insert/remove ids here, and "approve_user"/"reject_user" check membership directly
+        // (via `assert_is_current_admin`) rather than just requiring any badge of the resource,
+        // since the badge itself is never burned on removal.
+        operators: HashSet<NonFungibleId>,
+        // This is the ResourceAddress of the temporary badge given to prospective members who request to be members
+        // of this protocol.
+        temporary_badge_address: ResourceAddress,
+        // This is the badge that will be stored inside a vault of this component. This badge is used to mint or burn
+        // TemporaryBadge and UserBadge NFTs.
+        component_badge_vault: Vault,
+        // This will be a record of pending users requesting to be members of this protocol.
+        // This will record the stated username and the associated NFT ID of the TemporaryBadge NFT.
+        pending_users: HashMap<String, NonFungibleId>,
+        // This will be a record of approved users.
+        // This will record the TemporaryBadge NFT ID and the associated UserBadge NFT ID.
+        // The reason we set it like this is because when the approved member claims the UserBadge NFT, they will need
+        // to deposit the TemporaryBadge NFT so that it can be burnt and retrieve their UserBadge NFT. The component
+        // will determin which UserBadge NFT is owed to them based on the the TemporaryBadge NFT they deposit.
+        approved_users: HashMap<NonFungibleId, NonFungibleId>,
+        // TemporaryBadge NFT ids whose membership request was rejected. A rejected applicant still
+        // holds their TemporaryBadge (the component never took custody of it), so it is only
+        // actually burned once they deposit it back through "claim_user".
+        rejected_users: HashSet<NonFungibleId>,
+        // How many distinct custodian/operator badges must call "approve_user" on a username
+        // before a UserBadge is actually minted for them. 1 means the original single-approver
+        // behavior.
+        required_approvals: u8,
+        // Tracks, per pending username, which admin badge ids have already voted to approve it.
+        pending_approvals: HashMap<String, HashSet<NonFungibleId>>,
+        // This is the ResourceAddress of the UserBadge NFT that will allow members to access permissioned method call.
+        user_badge_address: ResourceAddress,
+        // This will be where the UserBadge NFT will be stored where approved members can claim their badges.
+        approved_users_vault: Vault,
+        // The ResourceAddress of the BidReceipt NFT minted whenever a member places a bid on the
+        // active auction. Held by the bidder until the auction ends, then redeemed via "reclaim_bid".
+        bid_receipt_address: ResourceAddress,
+        // Escrows the lot a seller put up for auction. `None` until the first auction is
+        // created; not reset when an auction ends.
+        auction_vault: Option<Vault>,
+        // The resource bids on the current auction must be paid in. Only its resource address
+        // is checked - its amount (or ids) is not, as each bidder sets their own amount in
+        // "place_bid". `None` until the first auction is created.
+        bid_currency: Option<AuctionResourceSpecifier>,
+        // Pools the bid currency handed over by every outstanding bid. A bidder's share of this
+        // vault is only ever released by redeeming the matching BidReceipt through
+        // `reclaim_bid`. `None` until the first auction is created; not reset when an auction ends.
+        bid_vault: Option<Vault>,
+        // Maps an outstanding BidReceipt's NFT id to the amount of bid currency it is entitled to reclaim.
+        bids: HashMap<NonFungibleId, Decimal>,
+        // The epoch from which the current auction can be finalized. `None` until the first
+        // auction is created; not reset when an auction ends.
+        auction_end_epoch: Option<u64>,
+        // The BidReceipt NFT id of the bid that is currently winning the active auction.
+        highest_bidder: Option<NonFungibleId>,
+        // The amount of the current highest bid. Reset to zero by "create_auction", so zero until
+        // the first bid on an auction.
+        highest_bid: Decimal,
+        // The UserBadge NFT id of the member who put the lot up for auction. Used to authorize
+        // `finalize_auction`.
+        seller_badge_id: Option<NonFungibleId>,
+        // Whether "finalize_auction" has already collected the seller's proceeds for the auction
+        // identified by `auction_end_epoch`. Reset to false by "create_auction". Needed because
+        // "finalize_auction" deliberately leaves `auction_end_epoch`/`bid_vault`/`auction_vault`
+        // untouched (outstanding BidReceipts still need them to redeem via "reclaim_bid"), so this
+        // flag - not an `Option` reset - is what prevents the seller from collecting twice.
+        auction_finalized: bool,
+        // When true, membership onboarding ("request_user", "approve_user", "claim_user") is
+        // frozen. Lets an admin halt the protocol without touching any badge.
+        paused: bool,
+        // UserBadge NFT ids that have been banned by an admin. Checked by every permissioned
+        // method that accepts a UserBadge Proof, so a compromised member can be revoked without
+        // burning their badge.
+        denylist: HashSet<NonFungibleId>,
     }
 
     impl UserAuth {
 
         // This function will return the ComponentAddress of the component to make it addressable.
         // It will also return us an admin badge through a Bucket.
-        pub fn instantiate_user_auth() -> ComponentAddress {
+        /// `required_approvals` is how many distinct custodian/operator badges must call "approve_user"
+        /// for the same username before a UserBadge is minted for them. Pass 1 for the original
+        /// single-approver behavior.
+        pub fn instantiate_user_auth(required_approvals: u8) -> (ComponentAddress, Bucket) {
+
+            assert!(required_approvals >= 1, "required_approvals must be at least 1!");
 
-            // The admin badge given to protocol owner.
-            let admin_badge: Bucket = ResourceBuilder::new_fungible()
-                .metadata("name", "Admin Badge")
-                .metadata("symbol", "AB")
-                // Only one will be given at instantiation of the component.
+            // The component badge to mint/burn TemporaryBadge/UserBadge/BidReceipt/OperatorBadge NFTs.
+            // This badge will be stored in one of the component vault.
+            let component_badge: Bucket = ResourceBuilder::new_fungible()
+                .metadata("name", "Component Badge")
+                .metadata("symbol", "CB")
+                // Only one will be sent to one of the component badge.
                 .initial_supply(1);
 
-            Self {
-                admin_badge_address: admin_badge.resource_address(),
+            // The resource that CustodianBadge NFTs belong to. Only a single one is ever minted,
+            // below, at instantiation - there is no top-level "add_custodian".
+            let custodian_badge_address: ResourceAddress = ResourceBuilder::new_non_fungible()
+                .metadata("name", "Custodian Badge")
+                .metadata("symbol", "CUST")
+                .mintable(rule!(require(component_badge.resource_address())), LOCKED)
+                .burnable(rule!(require(component_badge.resource_address())), LOCKED)
+                .no_initial_supply();
+
+            // The Custodian badge given to the protocol owner.
+            let custodian_badge_id: NonFungibleId = NonFungibleId::random();
+            let custodian_badge: Bucket = component_badge.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(custodian_badge_address);
+                resource_manager.mint_non_fungible(&custodian_badge_id, CustodianBadge {})
+            });
+            let custodian_badge_global_id: NonFungibleGlobalId =
+                NonFungibleGlobalId::new(custodian_badge_address, custodian_badge_id);
+
+            // The resource that OperatorBadge NFTs belong to. A custodian mints more of these
+            // through "add_operator".
+            let operator_badge_address: ResourceAddress = ResourceBuilder::new_non_fungible()
+                .metadata("name", "Operator Badge")
+                .metadata("symbol", "OP")
+                .mintable(rule!(require(component_badge.resource_address())), LOCKED)
+                .burnable(rule!(require(component_badge.resource_address())), LOCKED)
+                .no_initial_supply();
+
+            // The temporary badge given to prospective members.
+            let temporary_badge: ResourceAddress = ResourceBuilder::new_non_fungible()
+                .metadata("name", "Temporary Badge")
+                .metadata("symbol", "TB")
+                // Mint rule authorized to owner of the component badge.
+                .mintable(rule!(require(component_badge.resource_address())), LOCKED)
+                // Burn rule authorized to owner of the component badge.
+                .burnable(rule!(require(component_badge.resource_address())), LOCKED)
+                // No initial supply. Will be minted when "request_user" method is called.
+                .no_initial_supply();
+
+            let user_badge: ResourceAddress = ResourceBuilder::new_non_fungible()
+                .metadata("name", "User Badge")
+                .metadata("symbol", "UB")
+                // Mint rule authorized to owner of the component badge.
+                .mintable(rule!(require(component_badge.resource_address())), LOCKED)
+                // Burn rule authorized to owner of the component badge.
+                .burnable(rule!(require(component_badge.resource_address())), LOCKED)
+                // No initial supply. Will be minted when "approve_user" method is called.
+                .no_initial_supply();
+
+            // The BidReceipt given to members when they place a bid. It sits in the bidder's
+            // account between "place_bid" and the auction ending, then is redeemed (and burned)
+            // via "reclaim_bid".
+            let bid_receipt: ResourceAddress = ResourceBuilder::new_non_fungible()
+                .metadata("name", "Bid Receipt")
+                .metadata("symbol", "BR")
+                // Mint rule authorized to owner of the component badge.
+                .mintable(rule!(require(component_badge.resource_address())), LOCKED)
+                // Burn rule authorized to owner of the component badge.
+                .burnable(rule!(require(component_badge.resource_address())), LOCKED)
+                .no_initial_supply();
+
+            // "approve_user"/"reject_user" are deliberately left ungated here (falling through to
+            // the `default` rule below) and instead validate the caller's Proof manually, the same
+            // way "create_auction" validates a UserBadge Proof against `denylist`. A static
+            // `rule!(require(operator_badge_address))` check can't tell a current operator from one
+            // whose id was later struck from `operators` by "remove_operator" - it would accept any
+            // OperatorBadge forever, regardless of revocation.
+            let access_rule: AccessRules = AccessRules::new()
+                .method("pause", rule!(require(custodian_badge_global_id.clone())))
+                .method("unpause", rule!(require(custodian_badge_global_id.clone())))
+                .method("ban_user", rule!(require(custodian_badge_global_id.clone())))
+                .method("unban_user", rule!(require(custodian_badge_global_id.clone())))
+                .method("add_operator", rule!(require(custodian_badge_global_id.clone())))
+                .method("remove_operator", rule!(require(custodian_badge_global_id.clone())))
+                .method("set_tier", rule!(require(custodian_badge_global_id.clone())))
+                // All other methods are defaulted to be callable by anyone.
+                .default(rule!(allow_all));
+
+            let mut user_auth: UserAuthComponent = Self {
+                custodian_badge: custodian_badge_global_id,
+                operator_badge_address,
+                operators: HashSet::new(),
+                temporary_badge_address: temporary_badge,
+                component_badge_vault: Vault::with_bucket(component_badge),
+                pending_users: HashMap::new(),
+                approved_users: HashMap::new(),
+                rejected_users: HashSet::new(),
+                required_approvals,
+                pending_approvals: HashMap::new(),
+                user_badge_address: user_badge,
+                approved_users_vault: Vault::new(user_badge),
+                bid_receipt_address: bid_receipt,
+                auction_vault: None,
+                bid_currency: None,
+                bid_vault: None,
+                bids: HashMap::new(),
+                auction_end_epoch: None,
+                highest_bidder: None,
+                highest_bid: Decimal::zero(),
+                seller_badge_id: None,
+                auction_finalized: false,
+                paused: false,
+                denylist: HashSet::new(),
             }
-            .instantiate()
-            .globalize()
+            .instantiate();
+            user_auth.add_access_check(access_rule);
+            let user_auth_address: ComponentAddress = user_auth.globalize();
+
+            (user_auth_address, custodian_badge)
         }
 
-        pub fn request_user(&mut self, username: String) {
+        /// Asserts `admin_badge` is either the custodian badge or a currently-delegated operator
+        /// badge. Unlike the custodian badge (exactly one is ever minted, and it is never
+        /// revoked), an OperatorBadge's resource address alone isn't enough - its id must still be
+        /// in `self.operators`, since "remove_operator" only strikes the id from that set rather
+        /// than burning the badge.
+        fn assert_is_current_admin(&self, admin_badge: &Proof) {
+            if admin_badge.resource_address() == self.custodian_badge.resource_address() {
+                return;
+            }
+            assert!(
+                admin_badge.resource_address() == self.operator_badge_address
+                    && self.operators.contains(&admin_badge.non_fungible_id()),
+                "Incorrect or revoked admin badge!"
+            );
+        }
+
+        /// This method returns a TemporaryBadge NFT in a Bucket.
+        pub fn request_user(&mut self, username: String) -> Bucket {
+
+            assert!(!self.paused, "The protocol is currently paused!");
+
+            // This will mint us a temporary badge given to users.
+            let temporary_badge: Bucket = self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.temporary_badge_address);
+                resource_manager.mint_non_fungible(
+                    // The User id
+                    &NonFungibleId::random(),
+                    // The User data
+                    TemporaryBadge {
+                        username: username.clone(),
+                    },
+                )
+            });
 
+            // Inserts a record in our `pending_user` data field.
+            self.pending_users.insert(username, temporary_badge.non_fungible_id());
+
+            // Returns the TemporaryBadge NFT.
+            temporary_badge
         }
 
-        pub fn approve_user(&mut self, username: String) {
+        /// This method records a vote to approve a pending username from the calling custodian/operator
+        /// badge. Once `required_approvals` distinct admin badges have voted (1, by default, reproduces
+        /// the original single-approver behavior), a UserBadge NFT is minted and deposited into the
+        /// component's approved_user_vault, the TemporaryBadge NFT ID is recorded in the approved_user
+        /// data field, and the username is removed from the pending_users data field.
+        ///
+        /// Not gated via `AccessRules` - see the comment above the `access_rule` builder in
+        /// "instantiate_user_auth" for why the admin badge is validated manually instead.
+        pub fn approve_user(&mut self, username: String, admin_badge: Proof) {
+
+            assert!(!self.paused, "The protocol is currently paused!");
+            assert!(self.pending_users.contains_key(&username), "No such pending request!");
+            self.assert_is_current_admin(&admin_badge);
+
+            let votes: &mut HashSet<NonFungibleId> = self
+                .pending_approvals
+                .entry(username.clone())
+                .or_insert_with(HashSet::new);
+            votes.insert(admin_badge.non_fungible_id());
+
+            // Re-check every cast vote against the *current* custodian/operators, not just
+            // whoever was an admin at the moment they voted - an operator removed via
+            // "remove_operator" between casting their vote and this call shouldn't still count
+            // towards the threshold.
+            let valid_votes: u8 = self
+                .pending_approvals
+                .get(&username)
+                .unwrap()
+                .iter()
+                .filter(|id| **id == self.custodian_badge.non_fungible_id() || self.operators.contains(*id))
+                .count() as u8;
+
+            if valid_votes < self.required_approvals {
+                // Not enough still-valid admins have signed off on this username yet.
+                return;
+            }
+
+            let temporary_badge_id: &NonFungibleId = self.pending_users.get(&username).unwrap();
+
+            let user_badge: Bucket = self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.user_badge_address);
+                resource_manager.mint_non_fungible(
+                    // The User id
+                    &NonFungibleId::random(),
+                    // The User data
+                    UserBadge {
+                        username: username.clone(),
+                        expiry_epoch: Runtime::current_epoch() + DEFAULT_MEMBERSHIP_EPOCHS,
+                        tier: DEFAULT_TIER.to_string(),
+                    },
+                )
+            });
+
+            self.approved_users.insert(temporary_badge_id.clone(), user_badge.non_fungible_id());
 
+            self.approved_users_vault.put(user_badge);
+
+            self.pending_users.remove_entry(&username);
+            self.pending_approvals.remove(&username);
+
+        }
+
+        /// Rejects a pending membership request, removing its bookkeeping from pending_users and
+        /// pending_approvals. Custodian/operator-only, same as "approve_user" (and, like
+        /// "approve_user", validated manually rather than through `AccessRules` - see the comment
+        /// above the `access_rule` builder in "instantiate_user_auth"). The applicant's
+        /// TemporaryBadge is not burned here - the component was never given custody of it - but is
+        /// recorded so that "claim_user" will burn it and hand back an empty Bucket instead of
+        /// panicking if the applicant ever tries to deposit it.
+        pub fn reject_user(&mut self, username: String, admin_badge: Proof) {
+
+            self.assert_is_current_admin(&admin_badge);
+
+            let temporary_badge_id: NonFungibleId = self
+                .pending_users
+                .remove(&username)
+                .expect("No such pending request!");
+
+            self.rejected_users.insert(temporary_badge_id);
+            self.pending_approvals.remove(&username);
         }
 
-        pub fn claim_user(&mut self, temporary_badge: Bucket) {
+        /// Approved members will call this method to claim ther UserBadge NFT. To do so, they will need to deposit
+        /// their TemporaryBadg NFT. The UserBadge NFT will be returned in a Bucket.
+        pub fn claim_user(&mut self, temporary_badge: Bucket) -> Bucket {
+
+            assert!(!self.paused, "The protocol is currently paused!");
+
+            // This asserts that the TemporaryBadge NFT deposited was the TemporaryBadge NFT deposited into this
+            // component. This prevents a random person depositing an NFT that is not allowed in this protocol.
+            assert_eq!(
+                temporary_badge.resource_address(), self.temporary_badge_address,
+                "Badge does not belong to this protocol!"
+            );
+
+            // A rejected applicant has no UserBadge waiting for them - just discharge their
+            // TemporaryBadge and hand back an empty Bucket instead of panicking.
+            if self.rejected_users.remove(&temporary_badge.non_fungible_id()) {
+                self.component_badge_vault.authorize(|| temporary_badge.burn());
+                return Bucket::new(self.user_badge_address);
+            }
+
+            // This retrieves the UserBadge NFT based on the TemporaryBadge NFT ID assocaited with it.
+            let user_badge_id: &NonFungibleId = self.approved_users.get(&temporary_badge.non_fungible_id()).unwrap();
+
+            // This takes the UserBadge NFT from the component's approved_user_vault and puts it in a Bucket.
+            let user_badge: Bucket = self.approved_users_vault.take_non_fungible(user_badge_id);
+
+            self.approved_users.remove_entry(&temporary_badge.non_fungible_id());
+
+            // This authorizes the burn of the TemporaryBadge NFT deposited.
+            self.component_badge_vault.authorize(|| temporary_badge.burn());
+
+            // Returns the UserBadge NFT.
+            user_badge
+        }
+
+        /// Extends a member's UserBadge expiry by `epochs`, using the component badge to authorize the
+        /// `update_non_fungible_data` call. Takes the target member's badge id rather than a Proof of
+        /// it, like "set_tier", since it is the caller (a custodian or operator) who must be
+        /// authorized here, not the member being renewed - a member renewing themselves would make
+        /// the whole expiry feature pointless. Validated manually via `assert_is_current_admin`
+        /// rather than `AccessRules`, for the same revocation reason as "approve_user".
+        pub fn renew_membership(&mut self, user_badge_id: NonFungibleId, epochs: u64, admin_badge: Proof) {
+
+            self.assert_is_current_admin(&admin_badge);
+
+            self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.user_badge_address);
+                let mut data: UserBadge = resource_manager.get_non_fungible_data(&user_badge_id);
+                data.expiry_epoch += epochs;
+                resource_manager.update_non_fungible_data(&user_badge_id, data);
+            });
+        }
+
+        /// Upgrades or downgrades a member's tier. Custodian-only.
+        pub fn set_tier(&mut self, user_badge_id: NonFungibleId, tier: String) {
+
+            self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.user_badge_address);
+                let mut data: UserBadge = resource_manager.get_non_fungible_data(&user_badge_id);
+                data.tier = tier;
+                resource_manager.update_non_fungible_data(&user_badge_id, data);
+            });
+        }
+
+        /// This is an example method of what it would look like how members with the UserBadge NFT can access
+        /// permissioned method calls. They will need to provide a Proof of the UserBadge NFT. Unlike the "claim_user"
+        /// method call where the user would have to deposit the TemporaryBadge NFT, the Proof is a copy of the
+        /// UserBadge NFT that will drop at the end of the transaction. This is so the user does not have to physically
+        /// send the UserBadge NFT itself, only the Proof that they own the UserBadge NFT.
+        ///
+        /// This opens a new auction: `lot` is escrowed in `auction_vault` and will be handed to whoever redeems
+        /// the winning BidReceipt (see `reclaim_bid`) once the auction has reached `auction_end_epoch`.
+        /// `lot_specifier` must describe `lot` exactly; `bid_currency` describes the resource bids must be
+        /// paid in, allowing members to auction either fungible tokens or NFTs for payment in an arbitrary
+        /// resource.
+        pub fn create_auction(
+            &mut self,
+            user_badge: Proof,
+            lot: Bucket,
+            lot_specifier: AuctionResourceSpecifier,
+            bid_currency: AuctionResourceSpecifier,
+            auction_end_epoch: u64,
+        ) {
+
+            // This validates the Proof that the UserBadge NFT belongs to this protocol, similar to assertion in the
+            // "claim_user" method.
+            let validated_proof = user_badge
+                .validate_proof(ProofValidationMode::ValidateResourceAddress(self.user_badge_address))
+                .expect("Incorrect User Badge!");
+            assert!(
+                !self.denylist.contains(&validated_proof.non_fungible::<UserBadge>().id()),
+                "This member has been banned!"
+            );
+            assert!(
+                Runtime::current_epoch() < validated_proof.non_fungible::<UserBadge>().data().expiry_epoch,
+                "This membership has expired - renew it before calling this method!"
+            );
+
+            // An auction is "in progress" - and can't be superseded yet - until its end epoch has
+            // passed. `auction_end_epoch` itself is never reset by "finalize_auction" (stragglers
+            // still need it to call "reclaim_bid"), so this can't be a plain `is_none()` check.
+            assert!(
+                self.auction_end_epoch.map_or(true, |end| Runtime::current_epoch() >= end),
+                "An auction is already in progress!"
+            );
+            assert!(
+                auction_end_epoch > Runtime::current_epoch(),
+                "Auction end epoch must be in the future!"
+            );
+            assert!(lot_specifier.matches(&lot), "Lot does not match its resource specifier!");
+            assert!(
+                self.bid_vault.as_ref().map_or(true, |vault| vault.is_empty()),
+                "The previous auction's bids have not all been reclaimed yet!"
+            );
+            assert!(
+                self.auction_vault.as_ref().map_or(true, |vault| vault.is_empty()),
+                "The previous auction's lot has not been reclaimed yet!"
+            );
+
+            self.seller_badge_id = Some(validated_proof.non_fungible::<UserBadge>().id());
+            self.auction_vault = Some(Vault::with_bucket(lot));
+            self.bid_vault = Some(Vault::new(bid_currency.get_resource_address()));
+            self.bid_currency = Some(bid_currency);
+            self.auction_end_epoch = Some(auction_end_epoch);
+            self.highest_bid = Decimal::zero();
+            self.highest_bidder = None;
+            self.auction_finalized = false;
+        }
+
+        /// Places a bid in the active auction's accepted currency. A BidReceipt NFT recording the bid
+        /// amount is minted and returned to the caller, to be redeemed via `reclaim_bid` once the
+        /// auction ends.
+        pub fn place_bid(&mut self, bid: Bucket) -> Bucket {
+
+            assert!(self.auction_end_epoch.is_some(), "There is no active auction!");
+            assert_eq!(
+                bid.resource_address(),
+                self.bid_currency.as_ref().unwrap().get_resource_address(),
+                "Bids must be placed in the auction's accepted currency!"
+            );
+            assert!(
+                Runtime::current_epoch() < self.auction_end_epoch.unwrap(),
+                "This auction has already ended!"
+            );
+            assert!(bid.amount() > self.highest_bid, "Bid must exceed the current highest bid!");
+
+            let bid_amount: Decimal = bid.amount();
+            let receipt_id: NonFungibleId = NonFungibleId::random();
+
+            let bid_receipt: Bucket = self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.bid_receipt_address);
+                resource_manager.mint_non_fungible(
+                    &receipt_id,
+                    BidReceipt { bid_amount },
+                )
+            });
+
+            self.bids.insert(receipt_id.clone(), bid_amount);
+            self.bid_vault.as_mut().unwrap().put(bid);
+            self.highest_bid = bid_amount;
+            self.highest_bidder = Some(receipt_id);
+
+            bid_receipt
+        }
+
+        /// Redeems a BidReceipt after the auction has ended. The winning receipt is exchanged for the
+        /// auctioned lot; every other outstanding receipt is exchanged for a refund of its bid. Either way
+        /// the receipt is burned.
+        pub fn reclaim_bid(&mut self, bid_receipt: Bucket) -> Bucket {
+
+            assert_eq!(
+                bid_receipt.resource_address(), self.bid_receipt_address,
+                "Receipt does not belong to this protocol!"
+            );
+            assert!(
+                self.auction_end_epoch.is_some()
+                    && Runtime::current_epoch() >= self.auction_end_epoch.unwrap(),
+                "This auction has not ended yet!"
+            );
+
+            let receipt_id: NonFungibleId = bid_receipt.non_fungible_id();
+            let bid_amount: Decimal = self.bids.remove(&receipt_id).expect("This bid was already reclaimed!");
+
+            let payout: Bucket = if self.highest_bidder.as_ref() == Some(&receipt_id) {
+                // The winner reclaims the auctioned lot.
+                self.auction_vault.as_mut().unwrap().take_all()
+            } else {
+                // Every other bidder reclaims their bid.
+                self.bid_vault.as_mut().unwrap().take(bid_amount)
+            };
+
+            self.component_badge_vault.authorize(|| bid_receipt.burn());
+
+            payout
+        }
+
+        /// Callable by the seller once the auction has ended, to collect the winning bid. Requires the
+        /// same UserBadge that opened the auction via `create_auction`.
+        ///
+        /// Deliberately does not reset `auction_end_epoch`/`bid_vault`/`auction_vault`/`highest_bidder` -
+        /// bidders who haven't yet called `reclaim_bid` still need that state to redeem their
+        /// BidReceipt for a refund, or the lot if they won. `auction_finalized` is what prevents this
+        /// method from paying the seller out twice; `create_auction`'s drained-vault assertions are
+        /// what prevent a new auction starting before every BidReceipt has been redeemed.
+        pub fn finalize_auction(&mut self, user_badge: Proof) -> Bucket {
+
+            let validated_proof = user_badge
+                .validate_proof(ProofValidationMode::ValidateResourceAddress(self.user_badge_address))
+                .expect("Incorrect User Badge!");
+            assert!(
+                !self.denylist.contains(&validated_proof.non_fungible::<UserBadge>().id()),
+                "This member has been banned!"
+            );
+            assert!(
+                Runtime::current_epoch() < validated_proof.non_fungible::<UserBadge>().data().expiry_epoch,
+                "This membership has expired - renew it before calling this method!"
+            );
+
+            assert!(
+                self.auction_end_epoch.is_some()
+                    && Runtime::current_epoch() >= self.auction_end_epoch.unwrap(),
+                "This auction has not ended yet!"
+            );
+            assert_eq!(
+                self.seller_badge_id.as_ref(),
+                Some(&validated_proof.non_fungible::<UserBadge>().id()),
+                "Only the seller who opened this auction can finalize it!"
+            );
+            assert!(!self.auction_finalized, "Proceeds for this auction have already been collected!");
+
+            // If nobody ever bid, hand the unsold lot back to the seller instead of an empty
+            // payout; otherwise collect the winning bid (the winner separately reclaims the lot
+            // via "reclaim_bid").
+            let proceeds: Bucket = match &self.highest_bidder {
+                Some(_) => self.bid_vault.as_mut().unwrap().take(self.highest_bid),
+                None => self.auction_vault.as_mut().unwrap().take_all(),
+            };
+
+            self.auction_finalized = true;
+
+            proceeds
+        }
+
+        /// Freezes onboarding ("request_user", "approve_user", "claim_user"). Custodian-only.
+        pub fn pause(&mut self) {
+            self.paused = true;
+        }
+
+        /// Lifts a freeze put in place by "pause". Custodian-only.
+        pub fn unpause(&mut self) {
+            self.paused = false;
+        }
+
+        /// Bans a UserBadge NFT id so it can no longer pass the denylist check enforced by
+        /// permissioned methods such as "create_auction", without having to burn the badge.
+        /// Custodian-only.
+        pub fn ban_user(&mut self, user_badge_id: NonFungibleId) {
+            self.denylist.insert(user_badge_id);
+        }
+
+        /// Lifts a ban put in place by "ban_user". Custodian-only.
+        pub fn unban_user(&mut self, user_badge_id: NonFungibleId) {
+            self.denylist.remove(&user_badge_id);
+        }
+
+        /// Mints a new OperatorBadge, delegating "approve_user", "reject_user" and "renew_membership"
+        /// to whoever holds it. Custodian-only.
+        pub fn add_operator(&mut self) -> Bucket {
+
+            let operator_badge_id: NonFungibleId = NonFungibleId::random();
+
+            let operator_badge: Bucket = self.component_badge_vault.authorize(|| {
+                let resource_manager: &mut ResourceManager = borrow_resource_manager!(self.operator_badge_address);
+                resource_manager.mint_non_fungible(&operator_badge_id, OperatorBadge {})
+            });
+
+            self.operators.insert(operator_badge_id);
 
+            operator_badge
         }
 
-        pub fn create_auction(&mut self, user_badge: Proof) {
-            
+        /// Strikes an OperatorBadge id from the operator roster. Custodian-only.
+        pub fn remove_operator(&mut self, operator_badge_id: NonFungibleId) {
+            self.operators.remove(&operator_badge_id);
         }
     }
-}
\ No newline at end of file
+}